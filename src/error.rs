@@ -7,6 +7,7 @@ pub enum Error {
     WindowNotFound,
     DocumentNotFound,
     ElementNotFound,
+    StorageNotFound,
 }
 
 impl fmt::Display for Error {
@@ -17,6 +18,7 @@ impl fmt::Display for Error {
             Error::WindowNotFound => write!(f, "window not found"),
             Error::DocumentNotFound => write!(f, "document not found"),
             Error::ElementNotFound => write!(f, "element not found"),
+            Error::StorageNotFound => write!(f, "local storage not found"),
         }
     }
 }
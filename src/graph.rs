@@ -1,6 +1,8 @@
-use std::{ops::Deref, cell::RefCell, rc::Rc, cmp::Ordering, collections::HashMap};
+use std::{ops::Deref, cell::RefCell, rc::Rc, cmp::Ordering, collections::{BinaryHeap, HashMap, HashSet}};
 
-type NodeId = usize;
+use crate::Character;
+
+pub type NodeId = usize;
 
 #[derive(Debug, Clone)]
 pub struct ReadOnly<T> {
@@ -20,6 +22,34 @@ impl <T> From <Rc<RefCell<T>>> for ReadOnly<T> {
 }
 
 
+/// Wraps a node so `sort_by`'s user-supplied comparator can drive a
+/// `BinaryHeap`: the heap's max (next popped) is the comparator's minimum,
+/// so `cmp` delegates to the handler in reverse.
+struct HeapNode<'a, T> {
+    node: Rc<RefCell<Node<T>>>,
+    handler: &'a dyn Fn(&ReadOnly<Node<T>>, &ReadOnly<Node<T>>) -> Ordering,
+}
+
+impl<'a, T> PartialEq for HeapNode<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T> Eq for HeapNode<'a, T> {}
+
+impl<'a, T> PartialOrd for HeapNode<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> Ord for HeapNode<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.handler)(&self.node.clone().into(), &other.node.clone().into()).reverse()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Graph<T> {
     children: Vec<Rc<RefCell<Node<T>>>>,
@@ -33,27 +63,24 @@ impl <T> Graph<T> {
     }
 
     pub fn sort_by(&mut self, handler: impl Fn(&ReadOnly<Node<T>>, &ReadOnly<Node<T>>) -> Ordering) {
-        let mut order: Vec<Rc<RefCell<Node<T>>>> = Default::default();
-        let mut learnable_nodes = self.children.clone();
+        let mut order: Vec<Rc<RefCell<Node<T>>>> = Vec::with_capacity(self.children.len());
         let mut parents_learned: HashMap<NodeId, usize> = Default::default();
-        while !learnable_nodes.is_empty() {
-            learnable_nodes.sort_by(|a, b| {
-                handler(&a.clone().into(), &b.clone().into())
-            });
-            learnable_nodes.reverse();
-            let next = learnable_nodes.pop().unwrap();
-            {
-                let next = next.deref().borrow();
-                for child in &next.children {
-                    let learnable = {
-                        let child = child.deref().borrow();
-                        let count = parents_learned.entry(child.id).or_insert(0);
-                        *count += 1;
-                        *count == child.parents.len()
-                    };
-                    if learnable {
-                        learnable_nodes.push(child.clone());
-                    }
+
+        let mut learnable_nodes: BinaryHeap<HeapNode<T>> = self.children.iter()
+            .map(|node| HeapNode { node: node.clone(), handler: &handler })
+            .collect();
+
+        while let Some(HeapNode { node: next, .. }) = learnable_nodes.pop() {
+            let children = next.deref().borrow().children.clone();
+            for child in children {
+                let learnable = {
+                    let child = child.deref().borrow();
+                    let count = parents_learned.entry(child.id).or_insert(0);
+                    *count += 1;
+                    *count == child.parents.len()
+                };
+                if learnable {
+                    learnable_nodes.push(HeapNode { node: child, handler: &handler });
                 }
             }
             order.push(next);
@@ -64,6 +91,230 @@ impl <T> Graph<T> {
     pub fn nodes(&self) -> Vec<ReadOnly<Node<T>>> {
         self.children.iter().map(|c| ReadOnly::from(c.clone())).collect::<Vec<_>>()
     }
+
+    /// Groups every node into its connected component over the parent/child
+    /// edges, so self-contained families of nodes (e.g. kanji sharing
+    /// components) can be studied independently of the rest of the graph.
+    pub fn components(&self) -> Vec<Vec<ReadOnly<Node<T>>>> {
+        let all_nodes = collect_reachable(&self.children, |n| n.children.clone());
+        let index: HashMap<NodeId, usize> = all_nodes.iter().enumerate()
+            .map(|(i, node)| (node.deref().borrow().id, i))
+            .collect();
+
+        let mut sets = UnionFind::new(all_nodes.len());
+        for node in &all_nodes {
+            let node = node.deref().borrow();
+            let a = index[&node.id];
+            for child in &node.children {
+                let b = index[&child.deref().borrow().id];
+                sets.union(a, b);
+            }
+        }
+
+        let mut families: HashMap<usize, Vec<ReadOnly<Node<T>>>> = HashMap::new();
+        for node in all_nodes {
+            let root = sets.find(index[&node.deref().borrow().id]);
+            families.entry(root).or_insert_with(Vec::new).push(ReadOnly::from(node));
+        }
+
+        families.into_values().collect()
+    }
+
+    /// Returns the *nearest* shared ancestors of `a` and `b`: the minimal
+    /// elements of the intersection of their ancestor sets, i.e. those not
+    /// themselves an ancestor of another member of the intersection.
+    pub fn common_ancestors(&self, a: NodeId, b: NodeId) -> Vec<ReadOnly<Node<T>>> {
+        let all_nodes = collect_reachable(&self.children, |n| n.children.clone());
+        let find = |id: NodeId| all_nodes.iter().find(|n| n.deref().borrow().id == id).cloned();
+
+        let (node_a, node_b) = match (find(a), find(b)) {
+            (Some(node_a), Some(node_b)) => (node_a, node_b),
+            _ => return Vec::new(),
+        };
+
+        let ancestors_a = reachable_ids(&node_a.deref().borrow().parents, |n| n.parents.clone());
+        let ancestors_b = reachable_ids(&node_b.deref().borrow().parents, |n| n.parents.clone());
+        let shared: HashSet<NodeId> = ancestors_a.intersection(&ancestors_b).cloned().collect();
+
+        let minimal: HashSet<NodeId> = shared.iter().cloned().filter(|&x| {
+            !shared.iter().any(|&y| {
+                let node_y = find(y).unwrap();
+                let ancestors_y = reachable_ids(&node_y.deref().borrow().parents, |n| n.parents.clone());
+                y != x && ancestors_y.contains(&x)
+            })
+        }).collect();
+
+        all_nodes.into_iter()
+            .filter(|n| minimal.contains(&n.deref().borrow().id))
+            .map(ReadOnly::from)
+            .collect()
+    }
+}
+
+/// A disjoint-set over node positions in a fixed-size arena, used to group
+/// nodes into connected components without re-walking the graph per query.
+struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        UnionFind {
+            parent: (0..size).collect(),
+            rank: vec![0; size],
+        }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]);
+        }
+        self.parent[x]
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            Ordering::Less => self.parent[a] = b,
+            Ordering::Greater => self.parent[b] = a,
+            Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            },
+        }
+    }
+}
+
+impl Graph<Character> {
+    /// Returns the full transitive set of ancestors of `target`, ordered so a
+    /// node only appears once every ancestor of its own is already in the
+    /// list, so it can be studied as a sequential prerequisite path toward
+    /// `target`. Unlike `sort_by`, no comparator is applied: the order among
+    /// nodes whose ancestors are all already listed is not defined.
+    pub fn learning_path(&self, target: char) -> Vec<ReadOnly<Node<Character>>> {
+        let all_nodes = collect_reachable(&self.children, |n| n.children.clone());
+
+        let target_node = match all_nodes.iter().find(|n| n.deref().borrow().val.writing == target) {
+            Some(node) => node.clone(),
+            None => return Vec::new(),
+        };
+
+        let ancestor_ids: HashSet<NodeId> = {
+            let target_node = target_node.deref().borrow();
+            collect_reachable(&target_node.parents, |n| n.parents.clone()).iter()
+                .map(|n| n.deref().borrow().id)
+                .collect()
+        };
+
+        let ancestors = all_nodes.into_iter()
+            .filter(|n| ancestor_ids.contains(&n.deref().borrow().id))
+            .collect::<Vec<_>>();
+
+        learnable_order(&ancestors).into_iter().map(ReadOnly::from).collect()
+    }
+
+    /// Walks from a root matching `path[0]` down through successive children
+    /// matching each following char, addressing a node by its component
+    /// sequence instead of searching the whole graph. Returns `None` as soon
+    /// as a step has no matching child.
+    pub fn resolve_path(&self, path: &[char]) -> Option<ReadOnly<Node<Character>>> {
+        let (first, rest) = path.split_first()?;
+
+        let mut current = self.children.iter()
+            .find(|n| {
+                let n = n.deref().borrow();
+                n.parents.is_empty() && n.val.writing == *first
+            })?
+            .clone();
+
+        for &c in rest {
+            let next = current.deref().borrow().children.iter()
+                .find(|n| n.deref().borrow().val.writing == c)?
+                .clone();
+            current = next;
+        }
+
+        Some(ReadOnly::from(current))
+    }
+}
+
+/// Walks from `start` outward (via `neighbors`) and returns every reachable
+/// node exactly once, deduplicating shared nodes in a DAG.
+fn collect_reachable<T>(start: &[Rc<RefCell<Node<T>>>], neighbors: impl Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>>) -> Vec<Rc<RefCell<Node<T>>>> {
+    let mut visited: HashSet<NodeId> = HashSet::new();
+    let mut stack = start.to_vec();
+    let mut result = Vec::new();
+
+    while let Some(node) = stack.pop() {
+        let (id, next) = {
+            let node = node.deref().borrow();
+            (node.id, neighbors(&node))
+        };
+        if visited.insert(id) {
+            result.push(node);
+            stack.extend(next);
+        }
+    }
+
+    result
+}
+
+/// Same traversal as `collect_reachable`, but returns only the visited ids —
+/// cheaper when the caller just needs set membership (e.g. intersections).
+fn reachable_ids<T>(start: &[Rc<RefCell<Node<T>>>], neighbors: impl Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>>) -> HashSet<NodeId> {
+    let mut visited: HashSet<NodeId> = HashSet::new();
+    let mut stack = start.to_vec();
+
+    while let Some(node) = stack.pop() {
+        let (id, next) = {
+            let node = node.deref().borrow();
+            (node.id, neighbors(&node))
+        };
+        if visited.insert(id) {
+            stack.extend(next);
+        }
+    }
+
+    visited
+}
+
+/// Orders `nodes` so that every node appears only after all of its parents
+/// *within this subset* have already appeared.
+fn learnable_order<T>(nodes: &[Rc<RefCell<Node<T>>>]) -> Vec<Rc<RefCell<Node<T>>>> {
+    let ids: HashSet<NodeId> = nodes.iter().map(|n| n.deref().borrow().id).collect();
+
+    let mut queue: Vec<Rc<RefCell<Node<T>>>> = nodes.iter().filter(|n| {
+        let n = n.deref().borrow();
+        n.parents.iter().all(|p| !ids.contains(&p.deref().borrow().id))
+    }).cloned().collect();
+
+    let mut parents_seen: HashMap<NodeId, usize> = HashMap::new();
+    let mut order = Vec::new();
+
+    while let Some(next) = queue.pop() {
+        let children = next.deref().borrow().children.clone();
+        for child in children {
+            let (child_id, parent_count) = {
+                let child = child.deref().borrow();
+                (child.id, child.parents.iter().filter(|p| ids.contains(&p.deref().borrow().id)).count())
+            };
+            if !ids.contains(&child_id) {
+                continue;
+            }
+            let count = parents_seen.entry(child_id).or_insert(0);
+            *count += 1;
+            if *count == parent_count {
+                queue.push(child);
+            }
+        }
+        order.push(next);
+    }
+
+    order
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +335,10 @@ impl <T> Node<T> {
         }
     }
 
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
     pub fn val(&self) -> &T {
         &self.val
     }
@@ -104,21 +359,35 @@ impl <T> Node<T> {
         self.parents = parents;
     }
 
+    /// Count of *unique* descendants reachable from this node, correct even
+    /// when shared components make the DAG below it diamond-shaped.
     pub fn descendent_count(&self) -> usize {
-        let children = &self.children;
-        let mut len = children.len();
-        for child in children {
-            len += child.deref().borrow().descendent_count()
-        }
-        len
+        collect_reachable(&self.children, |n| n.children.clone()).len()
     }
 
+    /// Count of *unique* ancestors reachable from this node, correct even
+    /// when shared components make the DAG above it diamond-shaped.
     pub fn ancestor_count(&self) -> usize {
-        let parents = &self.parents;
-        let mut len = parents.len();
-        for parent in parents {
-            len += parent.deref().borrow().ancestor_count()
+        collect_reachable(&self.parents, |n| n.parents.clone()).len()
+    }
+}
+
+impl Node<Character> {
+    /// Enumerates every root-to-this-node component chain, as the sequence
+    /// of `writing` chars walked along the way, by recursing into each
+    /// parent's own root-to-parent paths and appending this node's char to
+    /// the end of each one. A node with more than one parent yields one
+    /// path per parent.
+    pub fn decomposition_paths(&self) -> Vec<Vec<char>> {
+        if self.parents.is_empty() {
+            return vec![vec![self.val.writing]];
         }
-        len
+
+        self.parents.iter().flat_map(|parent| {
+            parent.deref().borrow().decomposition_paths().into_iter().map(|mut path| {
+                path.push(self.val.writing);
+                path
+            })
+        }).collect()
     }
 }
\ No newline at end of file
@@ -6,14 +6,18 @@ use crate::error::Error;
 
 pub struct Html {
     pub characters: HtmlElement,
+    pub search: HtmlElement,
     pub overlay: OverlayHtml,
+    pub study: StudyHtml,
 }
 
 impl Html {
     pub fn new(document: &Document) -> Result<Self> {
         Ok(Html {
             characters: query(document, "#characters")?,
+            search: query(document, "#search")?,
             overlay: OverlayHtml::new(document)?,
+            study: StudyHtml::new(document)?,
         })
     }
 }
@@ -23,8 +27,14 @@ pub struct OverlayHtml {
     pub writing: HtmlElement,
     pub readings: HtmlElement,
     pub meaning: HtmlElement,
+    pub note: HtmlElement,
     pub parents: HtmlElement,
     pub children: HtmlElement,
+    pub senses: HtmlElement,
+    pub path: HtmlElement,
+    pub family: HtmlElement,
+    pub related: HtmlElement,
+    pub decomposition: HtmlElement,
 }
 
 impl OverlayHtml {
@@ -34,8 +44,44 @@ impl OverlayHtml {
             writing: query(&document, "#overlay .writing")?,
             readings: query(&document, "#overlay .readings")?,
             meaning: query(&document, "#overlay .meaning")?,
+            note: query(&document, "#overlay .note")?,
             parents: query(&document, "#overlay .parents")?,
             children: query(&document, "#overlay .children")?,
+            senses: query(&document, "#overlay .senses")?,
+            path: query(&document, "#overlay .path")?,
+            family: query(&document, "#overlay .family")?,
+            related: query(&document, "#overlay .related")?,
+            decomposition: query(&document, "#overlay .decomposition")?,
+        })
+    }
+}
+
+pub struct StudyHtml {
+    pub toggle: HtmlElement,
+    pub panel: HtmlElement,
+    pub due_count: HtmlElement,
+    pub card: HtmlElement,
+    pub writing: HtmlElement,
+    pub readings: HtmlElement,
+    pub meaning: HtmlElement,
+    pub grade_buttons: Vec<HtmlElement>,
+}
+
+impl StudyHtml {
+    pub fn new(document: &Document) -> Result<Self> {
+        let grade_buttons = (0..=5)
+            .map(|quality| query(document, &format!("#study .grade[data-quality=\"{}\"]", quality)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(StudyHtml {
+            toggle: query(document, "#studyToggle")?,
+            panel: query(document, "#study")?,
+            due_count: query(document, "#study .dueCount")?,
+            card: query(document, "#study .card")?,
+            writing: query(document, "#study .writing")?,
+            readings: query(document, "#study .readings")?,
+            meaning: query(document, "#study .meaning")?,
+            grade_buttons,
         })
     }
 }
@@ -1,18 +1,22 @@
-use std::{collections::{HashMap, HashSet}, rc::Rc, cell::{RefCell}};
+use std::{cmp::Ordering, collections::{HashMap, HashSet}, rc::Rc, cell::{RefCell}};
 use enclose::enclose;
 use error::Error;
 use graph::{ReadOnly, Node, Graph};
 use html::Html;
+use js_sys::Date;
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::{JsFuture};
 use wasm_mutex::Mutex;
-use web_sys::{RequestInit, RequestMode, Request, Response, HtmlElement, MouseEvent, Document};
+use web_sys::{RequestInit, RequestMode, Request, Response, HtmlElement, HtmlInputElement, InputEvent, MouseEvent, Document};
 
 use regex::Regex;
 
 mod html;
 mod error;
 mod graph;
+mod study;
+mod sanitize;
+mod registry;
 
 #[wasm_bindgen]
 extern "C" {
@@ -41,19 +45,35 @@ pub struct Character {
 
 struct State {
     overlay_click_closures: Vec<Closure<dyn FnMut(MouseEvent)>>,
+    review_store: study::ReviewStore,
+    order: Vec<ReadOnly<Node<Character>>>,
+    study_queue: Vec<ReadOnly<Node<Character>>>,
+    study_index: usize,
+    character_elements: Vec<(ReadOnly<Node<Character>>, HtmlElement)>,
+    terms: Vec<Term>,
+    term_index: HashMap<char, Vec<usize>>,
+    term_registry: registry::Registry,
+    graph: Graph<Character>,
+    family_index: HashMap<char, Vec<ReadOnly<Node<Character>>>>,
+    last_viewed: Option<ReadOnly<Node<Character>>>,
 }
 
 impl State {
-    pub fn new() -> Self {
-        State {
+    pub fn new() -> Result<Self> {
+        Ok(State {
             overlay_click_closures: Default::default(),
-        }
-    }
-}
-
-impl Default for State {
-    fn default() -> Self {
-        State::new()
+            review_store: study::ReviewStore::load()?,
+            order: Default::default(),
+            study_queue: Default::default(),
+            study_index: 0,
+            character_elements: Default::default(),
+            terms: Default::default(),
+            term_index: Default::default(),
+            term_registry: registry::Registry::build(&[]),
+            graph: Graph::new(Default::default()),
+            family_index: Default::default(),
+            last_viewed: None,
+        })
     }
 }
 
@@ -94,7 +114,7 @@ pub fn document() -> Result<Document> {
 async fn init() -> Result<()> {
     let document = crate::document()?;
     let html = Rc::new(Html::new(&document)?);
-    let state = Rc::new(Mutex::new(State::new()));
+    let state = Rc::new(Mutex::new(State::new()?));
 
     set_on_click(&html.overlay.div, |event| {
         event.stop_propagation();
@@ -104,6 +124,27 @@ async fn init() -> Result<()> {
         html.overlay.wrapper.class_list().add_1("hidden").unwrap();
     })).forget();
 
+    set_on_click(&html.study.toggle, enclose!((html, state) move |_event| {
+        wasm_bindgen_futures::spawn_local(enclose!((html, state) async move {
+            toggle_study(html.clone(), state.clone()).await.unwrap();
+        }));
+    })).forget();
+
+    for quality in 0..=5u8 {
+        let button = &html.study.grade_buttons[quality as usize];
+        set_on_click(button, enclose!((html, state) move |_event| {
+            wasm_bindgen_futures::spawn_local(enclose!((html, state) async move {
+                grade_study_card(html.clone(), state.clone(), quality).await.unwrap();
+            }));
+        })).forget();
+    }
+
+    set_on_input(&html.search, enclose!((state) move |event: InputEvent| {
+        wasm_bindgen_futures::spawn_local(enclose!((state, event) async move {
+            apply_search(state.clone(), &event).await.unwrap();
+        }));
+    })).forget();
+
     let characters = fetch_local_text("/data/characters.txt").await?;
     let mut characters = parse_characters(&characters)?;
 
@@ -122,7 +163,13 @@ async fn init() -> Result<()> {
         b_score.cmp(&a_score)
     });
 
-    characters.nodes().iter().try_for_each(enclose!((html) move |node| {
+    let term_index = build_term_index(&terms);
+    let term_registry = registry::Registry::build(&terms);
+    let family_index = build_family_index(&characters);
+
+    let mut character_elements: Vec<(ReadOnly<Node<Character>>, HtmlElement)> = Default::default();
+    characters.nodes().iter().try_for_each(|node| {
+        let html = html.clone();
         let character_el = {
             let node = node.borrow();
             let val = node.val();
@@ -143,15 +190,27 @@ async fn init() -> Result<()> {
             }));
         })).forget();
         html.characters.append_child(&character_el)?;
+        character_elements.push((node.clone(), character_el));
         Ok::<(), error::Error>(())
-    }))?;
+    })?;
+
+    {
+        let mut locked_state = state.lock().await;
+        locked_state.order = characters.nodes();
+        locked_state.character_elements = character_elements;
+        locked_state.term_index = term_index;
+        locked_state.terms = terms;
+        locked_state.term_registry = term_registry;
+        locked_state.family_index = family_index;
+        locked_state.graph = characters;
+    }
 
     log("complete");
     Ok(())
 }
 
-async fn on_character_click(html: Rc<Html>, state: Rc<Mutex<State>>, node: &ReadOnly<Node<Character>>) -> Result<()> {
-    let node = node.borrow();
+async fn on_character_click(html: Rc<Html>, state: Rc<Mutex<State>>, node_ref: &ReadOnly<Node<Character>>) -> Result<()> {
+    let node = node_ref.borrow();
     let character = node.val();
 
     html.overlay.writing.set_text_content(Some(&character.writing.to_string()));
@@ -160,6 +219,18 @@ async fn on_character_click(html: Rc<Html>, state: Rc<Mutex<State>>, node: &Read
 
     let document = document()?;
 
+    match &character.note {
+        Some(note) => {
+            let sanitized = sanitize::sanitize_fragment(&document, note)?;
+            html.overlay.note.set_inner_html(&sanitized);
+            html.overlay.note.class_list().remove_1("hidden")?;
+        },
+        None => {
+            html.overlay.note.set_inner_html("");
+            html.overlay.note.class_list().add_1("hidden")?;
+        },
+    }
+
     {
         let mut locked_state = state.lock().await;
         locked_state.overlay_click_closures.clear();
@@ -177,6 +248,28 @@ async fn on_character_click(html: Rc<Html>, state: Rc<Mutex<State>>, node: &Read
             locked_state.overlay_click_closures.push(closure);
             Ok::<(), Error>(())
         }))?;
+
+        render_senses(&document, &html.overlay.senses, character.writing, &locked_state.terms, &locked_state.term_index)?;
+        render_decomposition_paths(&document, &html.overlay.decomposition, &node.decomposition_paths())?;
+
+        let path = locked_state.graph.learning_path(character.writing);
+        let path_closures = render_character_chips(&document, html.clone(), state.clone(), &html.overlay.path, &path)?;
+        locked_state.overlay_click_closures.extend(path_closures);
+
+        let family = locked_state.family_index.get(&character.writing).cloned().unwrap_or_default();
+        let family_closures = render_character_chips(&document, html.clone(), state.clone(), &html.overlay.family, &family)?;
+        locked_state.overlay_click_closures.extend(family_closures);
+
+        let related = match &locked_state.last_viewed {
+            Some(prev) if prev.borrow().id() != node.id() => {
+                locked_state.graph.common_ancestors(prev.borrow().id(), node.id())
+            },
+            _ => Vec::new(),
+        };
+        let related_closures = render_character_chips(&document, html.clone(), state.clone(), &html.overlay.related, &related)?;
+        locked_state.overlay_click_closures.extend(related_closures);
+
+        locked_state.last_viewed = Some(node_ref.clone());
     }
     
     html.overlay.wrapper.class_list().remove_1("hidden")?;
@@ -205,6 +298,136 @@ fn add_overlay_relative(document: &Document, html: Rc<Html>, state: Rc<Mutex<Sta
     Ok(closure)
 }
 
+/// Renders every sense of every term written with `writing`, grouping each
+/// sense's part-of-speech tags apart from its glosses so the overlay shows
+/// grammatical category instead of the old flat `meaning` join.
+fn render_senses(document: &Document, container: &HtmlElement, writing: char, terms: &[Term], term_index: &HashMap<char, Vec<usize>>) -> Result<()> {
+    container.set_inner_html("");
+    let indices = match term_index.get(&writing) {
+        Some(indices) => indices,
+        None => return Ok(()),
+    };
+
+    for &i in indices {
+        for sense in &terms[i].senses {
+            let sense_el: HtmlElement = document.create_element("div")?.unchecked_into();
+            sense_el.class_list().add_1("sense")?;
+
+            if !sense.pos.is_empty() {
+                let pos_el: HtmlElement = document.create_element("span")?.unchecked_into();
+                pos_el.class_list().add_1("pos")?;
+                pos_el.set_text_content(Some(&sense.pos.join(", ")));
+                sense_el.append_child(&pos_el)?;
+            }
+
+            let gloss_el: HtmlElement = document.create_element("span")?.unchecked_into();
+            gloss_el.class_list().add_1("glosses")?;
+            gloss_el.set_text_content(Some(&sense.glosses.join("; ")));
+            sense_el.append_child(&gloss_el)?;
+
+            container.append_child(&sense_el)?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders `nodes` into `container` as clickable chips that reopen the
+/// overlay on the chip's own character, the same way parent/child relatives
+/// do, so callers with a plain node list (learning path, family) don't have
+/// to duplicate the wiring.
+fn render_character_chips(document: &Document, html: Rc<Html>, state: Rc<Mutex<State>>, container: &HtmlElement, nodes: &[ReadOnly<Node<Character>>]) -> Result<Vec<Closure<dyn FnMut(MouseEvent)>>> {
+    container.set_inner_html("");
+    nodes.iter().map(|node| {
+        let el: HtmlElement = document.create_element("div")?.unchecked_into();
+        el.set_text_content(Some(&node.borrow().val().writing.to_string()));
+        container.append_child(&el)?;
+        Ok(set_on_click(&el, enclose!((html, state, node) move |_event| {
+            wasm_bindgen_futures::spawn_local(enclose!((html, state, node) async move {
+                on_character_click(html.clone(), state.clone(), &node).await.unwrap();
+            }));
+        })))
+    }).collect()
+}
+
+/// Renders every root-to-here component chain from `Node::decomposition_paths`
+/// as its own line, so a multi-parent kanji shows one chain per way it can
+/// be built up from primitives instead of only its direct parents.
+fn render_decomposition_paths(document: &Document, container: &HtmlElement, paths: &[Vec<char>]) -> Result<()> {
+    container.set_inner_html("");
+    for path in paths {
+        let line: String = path.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" → ");
+        let el: HtmlElement = document.create_element("div")?.unchecked_into();
+        el.set_text_content(Some(&line));
+        container.append_child(&el)?;
+    }
+    Ok(())
+}
+
+async fn toggle_study(html: Rc<Html>, state: Rc<Mutex<State>>) -> Result<()> {
+    let hidden = html.study.panel.class_list().contains("hidden");
+    if hidden {
+        open_study(html, state).await
+    } else {
+        html.study.panel.class_list().add_1("hidden")?;
+        Ok(())
+    }
+}
+
+async fn open_study(html: Rc<Html>, state: Rc<Mutex<State>>) -> Result<()> {
+    let mut locked_state = state.lock().await;
+    let now = Date::now();
+    let order = locked_state.order.clone();
+    locked_state.study_queue = due_queue(&order, &locked_state.review_store, now);
+    locked_state.study_index = 0;
+    render_study_queue(&html, &locked_state)?;
+    html.study.panel.class_list().remove_1("hidden")?;
+    Ok(())
+}
+
+async fn grade_study_card(html: Rc<Html>, state: Rc<Mutex<State>>, quality: u8) -> Result<()> {
+    let mut locked_state = state.lock().await;
+    let now = Date::now();
+    if let Some(node) = locked_state.study_queue.get(locked_state.study_index).cloned() {
+        let writing = node.borrow().val().writing;
+        locked_state.review_store.record_review(writing, quality, now);
+        locked_state.review_store.save()?;
+        locked_state.study_index += 1;
+    }
+    render_study_queue(&html, &locked_state)?;
+    Ok(())
+}
+
+/// A character is due for study once its own review is due *and* every parent
+/// radical it builds on has already been learned, so the queue always respects
+/// the same prerequisite ordering the graph was sorted by.
+fn due_queue(order: &[ReadOnly<Node<Character>>], store: &study::ReviewStore, now: f64) -> Vec<ReadOnly<Node<Character>>> {
+    order.iter().filter(|node| {
+        let node = node.borrow();
+        if !store.is_due(node.val().writing, now) {
+            return false;
+        }
+        node.parents().iter().all(|p| store.is_learned(p.borrow().val().writing))
+    }).cloned().collect()
+}
+
+fn render_study_queue(html: &Html, state: &State) -> Result<()> {
+    let remaining = state.study_queue.len().saturating_sub(state.study_index);
+    html.study.due_count.set_text_content(Some(&remaining.to_string()));
+
+    if let Some(node) = state.study_queue.get(state.study_index) {
+        let node = node.borrow();
+        let character = node.val();
+        html.study.card.class_list().remove_1("hidden")?;
+        html.study.writing.set_text_content(Some(&character.writing.to_string()));
+        html.study.readings.set_text_content(Some(&character.readings.join("、")));
+        html.study.meaning.set_text_content(Some(&character.meaning));
+    } else {
+        html.study.card.class_list().add_1("hidden")?;
+        html.study.meaning.set_text_content(Some("all caught up"));
+    }
+    Ok(())
+}
+
 fn set_on_click<F>(el: &HtmlElement, handler: F) -> Closure<dyn FnMut(MouseEvent)>
     where
         F: FnMut(MouseEvent) + 'static {
@@ -213,15 +436,98 @@ fn set_on_click<F>(el: &HtmlElement, handler: F) -> Closure<dyn FnMut(MouseEvent
             c
         }
 
+fn set_on_input<F>(el: &HtmlElement, handler: F) -> Closure<dyn FnMut(InputEvent)>
+    where
+        F: FnMut(InputEvent) + 'static {
+            let c = Closure::wrap(Box::new(handler) as Box<dyn FnMut(InputEvent)>);
+            el.set_oninput(Some(c.as_ref().unchecked_ref()));
+            c
+        }
+
 #[derive(Debug, Clone)]
 pub struct Term {
     pub id: String,
     pub writings: Vec<String>,
     pub readings: Option<Vec<String>>,
     pub meanings: Vec<String>,
+    pub senses: Vec<Sense>,
     pub popular: bool,
 }
 
+/// A single numbered sense of a term, as found between `(1)`/`(2)` markers in
+/// the EDICT2 gloss fields.
+#[derive(Debug, Clone, Default)]
+pub struct Sense {
+    pub pos: Vec<String>,
+    pub glosses: Vec<String>,
+    pub tags: Vec<String>,
+    pub xrefs: Vec<String>,
+}
+
+impl Sense {
+    fn is_empty(&self) -> bool {
+        self.pos.is_empty() && self.glosses.is_empty() && self.tags.is_empty() && self.xrefs.is_empty()
+    }
+}
+
+// Parts-of-speech tags recognized by EDICT2/JMdict; anything else found in a
+// leading `(...)` tag group is treated as a usage note rather than a pos.
+const POS_TAGS: &[&str] = &[
+    "n", "vs", "vt", "vi", "adj-i", "adj-na", "adj-no", "adj-pn", "adj-t", "adj-f",
+    "adv", "adv-to", "aux", "aux-v", "aux-adj", "conj", "ctr", "exp", "int", "num",
+    "pn", "pref", "prt", "suf", "v1", "v5", "v5aru", "v5b", "v5g", "v5k", "v5m",
+    "v5n", "v5r", "v5s", "v5t", "v5u", "vk", "vn", "vr", "vs-i", "vs-s", "vz",
+];
+
+fn parse_senses(gloss_fields: &[&str]) -> Vec<Sense> {
+    let sense_num_re = Regex::new(r"^\((\d+)\)\s*").unwrap();
+    let xref_re = Regex::new(r"(?i)^\(See\s+([^)]+)\)\s*").unwrap();
+    let field_tag_re = Regex::new(r"^\{([^{}]+)\}\s*").unwrap();
+    let tag_group_re = Regex::new(r"^\(([^()]+)\)\s*").unwrap();
+
+    let mut senses = Vec::new();
+    let mut current = Sense::default();
+
+    for field in gloss_fields {
+        let mut rest = *field;
+        loop {
+            if let Some(m) = sense_num_re.captures(rest) {
+                if !current.is_empty() {
+                    senses.push(std::mem::take(&mut current));
+                }
+                rest = &rest[m.get(0).unwrap().end()..];
+            } else if let Some(m) = xref_re.captures(rest) {
+                current.xrefs.push(m[1].trim().to_string());
+                rest = &rest[m.get(0).unwrap().end()..];
+            } else if let Some(m) = field_tag_re.captures(rest) {
+                current.tags.push(m[1].trim().to_string());
+                rest = &rest[m.get(0).unwrap().end()..];
+            } else if let Some(m) = tag_group_re.captures(rest) {
+                for tag in m[1].split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+                    if POS_TAGS.contains(&tag.as_str()) {
+                        current.pos.push(tag);
+                    } else {
+                        current.tags.push(tag);
+                    }
+                }
+                rest = &rest[m.get(0).unwrap().end()..];
+            } else {
+                break;
+            }
+        }
+        let gloss = rest.trim();
+        if !gloss.is_empty() {
+            current.glosses.push(gloss.to_string());
+        }
+    }
+
+    if !current.is_empty() {
+        senses.push(current);
+    }
+
+    senses
+}
+
 fn group_terms_by_chars(terms: &[Term]) -> HashMap<char, Vec<&Term>> {
     terms.iter().fold(HashMap::new(), |mut acc, term| {
         let chars = term.writings.iter().fold(HashSet::new(), |mut acc, writing| {
@@ -239,6 +545,157 @@ fn group_terms_by_chars(terms: &[Term]) -> HashMap<char, Vec<&Term>> {
     })
 }
 
+fn build_term_index(terms: &[Term]) -> HashMap<char, Vec<usize>> {
+    terms.iter().enumerate().fold(HashMap::new(), |mut acc, (i, term)| {
+        let chars = term.writings.iter().fold(HashSet::new(), |mut acc, writing| {
+            writing.chars().for_each(|c| {
+                acc.insert(c);
+            });
+            acc
+        });
+
+        chars.iter().for_each(|c| {
+            acc.entry(*c).or_insert_with(Vec::new).push(i);
+        });
+        acc
+    })
+}
+
+/// Groups the graph into connected "families" via `Graph::components`, then
+/// indexes each member's writing to its family peers so the overlay can show
+/// a self-contained study list instead of only the single global order.
+fn build_family_index(graph: &Graph<Character>) -> HashMap<char, Vec<ReadOnly<Node<Character>>>> {
+    let mut family_index: HashMap<char, Vec<ReadOnly<Node<Character>>>> = HashMap::new();
+    for family in graph.components() {
+        for node in &family {
+            let id = node.borrow().id();
+            let peers = family.iter()
+                .filter(|other| other.borrow().id() != id)
+                .cloned()
+                .collect();
+            family_index.insert(node.borrow().val().writing, peers);
+        }
+    }
+    family_index
+}
+
+/// A single token of a structured search query, matched against a character
+/// with logical AND across all tokens in the query.
+#[derive(Debug, Clone)]
+pub enum Predicate {
+    Reading(String),
+    Meaning(String),
+    StrokeCount(Ordering, u8),
+    IsRadical(bool),
+    Contains(String),
+    Word(String),
+}
+
+// `popular:true` is a query-wide mode switch (it restricts every `word:`
+// lookup to entries `parse_term` flagged popular from their `(P)` field) rather than a
+// predicate in its own right, so it's consumed separately from the rest of
+// the tokens instead of becoming a no-op Contains match.
+fn parse_query(query: &str) -> Vec<Predicate> {
+    query.split_whitespace().filter(|token| !token.starts_with("popular:") && !token.starts_with("path:")).map(|token| {
+        if let Some(value) = token.strip_prefix("reading:") {
+            Predicate::Reading(value.to_string())
+        } else if let Some(value) = token.strip_prefix("meaning:") {
+            Predicate::Meaning(value.to_string())
+        } else if let Some(value) = token.strip_prefix("radical:") {
+            Predicate::IsRadical(value.eq_ignore_ascii_case("true"))
+        } else if let Some(value) = token.strip_prefix("strokes:") {
+            parse_stroke_predicate(value)
+        } else if let Some(value) = token.strip_prefix("word:") {
+            Predicate::Word(value.to_string())
+        } else {
+            Predicate::Contains(token.to_string())
+        }
+    }).collect()
+}
+
+fn wants_popular_only(query: &str) -> bool {
+    query.split_whitespace().any(|token| token == "popular:true")
+}
+
+/// `path:` addresses a single node by its root-to-target component sequence
+/// (e.g. `path:氵海` to jump straight to 海) rather than by any property of
+/// the character itself, so — like `popular:true` — it's resolved once
+/// against the graph up front instead of becoming a per-character Predicate.
+fn parse_path_query(query: &str) -> Option<Vec<char>> {
+    query.split_whitespace().find_map(|token| token.strip_prefix("path:")).map(|value| value.chars().collect())
+}
+
+// Stroke counts are small unsigned integers, so an inclusive bound like
+// `<=5` is normalized to the equivalent strict bound `<6` up front, which
+// lets a single `(Ordering, u8)` pair fully describe the comparison.
+fn parse_stroke_predicate(value: &str) -> Predicate {
+    if let Some(n) = value.strip_prefix("<=").and_then(|s| s.parse::<u8>().ok()) {
+        Predicate::StrokeCount(Ordering::Less, n.saturating_add(1))
+    } else if let Some(n) = value.strip_prefix(">=").and_then(|s| s.parse::<u8>().ok()) {
+        Predicate::StrokeCount(Ordering::Greater, n.saturating_sub(1))
+    } else if let Some(n) = value.strip_prefix('<').and_then(|s| s.parse::<u8>().ok()) {
+        Predicate::StrokeCount(Ordering::Less, n)
+    } else if let Some(n) = value.strip_prefix('>').and_then(|s| s.parse::<u8>().ok()) {
+        Predicate::StrokeCount(Ordering::Greater, n)
+    } else if let Ok(n) = value.trim_start_matches('=').parse::<u8>() {
+        Predicate::StrokeCount(Ordering::Equal, n)
+    } else {
+        Predicate::Contains(format!("strokes:{}", value))
+    }
+}
+
+fn character_matches(node: &ReadOnly<Node<Character>>, predicates: &[Predicate], terms: &[Term], term_index: &HashMap<char, Vec<usize>>, registry: &registry::Registry) -> bool {
+    let node = node.borrow();
+    let character = node.val();
+
+    predicates.iter().all(|predicate| match predicate {
+        Predicate::Reading(value) => character.readings.iter().any(|r| r.contains(value.as_str())),
+        Predicate::Meaning(value) => {
+            let value = value.to_lowercase();
+            character.meaning.to_lowercase().contains(&value) || term_index.get(&character.writing).map(|idxs| {
+                idxs.iter().any(|&i| terms[i].meanings.iter().any(|m| m.to_lowercase().contains(&value)))
+            }).unwrap_or(false)
+        },
+        Predicate::StrokeCount(ordering, value) => character.stroke_count.cmp(value) == *ordering,
+        Predicate::IsRadical(value) => character.is_radical == *value,
+        Predicate::Contains(value) => {
+            let value = value.to_lowercase();
+            character.writing.to_string().contains(&value)
+                || character.meaning.to_lowercase().contains(&value)
+                || character.readings.iter().any(|r| r.to_lowercase().contains(&value))
+        },
+        Predicate::Word(value) => {
+            let matches = |found: Vec<&Term>| found.iter().any(|t| t.writings.iter().any(|w| w.contains(character.writing)));
+            matches(registry.search_meaning(value, terms)) || matches(registry.by_reading(value, terms)) || matches(registry.by_writing(value, terms))
+        },
+    })
+}
+
+async fn apply_search(state: Rc<Mutex<State>>, event: &InputEvent) -> Result<()> {
+    let input: HtmlInputElement = event.target().ok_or(Error::ElementNotFound)?.unchecked_into();
+    let query = input.value();
+    let predicates = parse_query(&query);
+
+    let mut locked_state = state.lock().await;
+    locked_state.term_registry.set_popular_only(wants_popular_only(&query));
+
+    let path_target = parse_path_query(&query)
+        .and_then(|path| locked_state.graph.resolve_path(&path))
+        .map(|node| node.borrow().id());
+
+    locked_state.character_elements.iter().try_for_each(|(node, el)| {
+        let matches = character_matches(node, &predicates, &locked_state.terms, &locked_state.term_index, &locked_state.term_registry)
+            && path_target.map(|id| node.borrow().id() == id).unwrap_or(true);
+        if matches {
+            el.class_list().remove_1("hidden")
+        } else {
+            el.class_list().add_1("hidden")
+        }
+    })?;
+
+    Ok(())
+}
+
 fn parse_terms(s: &str) -> Result<Vec<Term>> {
     let lines = s.split("\n").map(|s| s.trim());
     let terms: Result<Vec<Term>> = lines.enumerate().try_fold(Vec::new(),|mut acc, (i, line)| {
@@ -255,14 +712,16 @@ fn parse_term(s: &str) -> Result<Term> {
     let fields = s.split("/").filter(|s| !s.is_empty()).collect::<Vec<_>>();
     let id = fields[fields.len()-1].to_string();
     let mut popular = false;
-    let meanings = fields[1..fields.len()-1].iter().filter_map(|s| {
+    let gloss_fields = fields[1..fields.len()-1].iter().filter_map(|s| {
         if s.eq_ignore_ascii_case("(P)") {
             popular = true;
             None
         } else {
-            Some(s.to_string())
+            Some(*s)
         }
-    }).collect();
+    }).collect::<Vec<_>>();
+    let meanings = gloss_fields.iter().map(|s| s.to_string()).collect();
+    let senses = parse_senses(&gloss_fields);
     let (writings, readings) = if let Some(cap) = Regex::new(WRITING_READING_RE).unwrap().captures(fields[0]) {
         let writings = cap[1].split(";").map(|s| s.trim().to_string()).collect();
         let readings = Some(cap[2].split(";").map(|s| s.trim().to_string()).collect());
@@ -279,6 +738,7 @@ fn parse_term(s: &str) -> Result<Term> {
         writings,
         readings,
         meanings,
+        senses,
     })
 }
 
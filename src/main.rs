@@ -1,4 +1,4 @@
-use std::{collections::{HashMap, HashSet}, rc::Rc, cell::{RefCell}, ops::Deref, cmp::Ordering, io::{BufReader, BufRead}};
+use std::{collections::{BinaryHeap, HashMap, HashSet, VecDeque}, rc::Rc, cell::{RefCell}, ops::Deref, cmp::Ordering, io::{BufReader, BufRead}};
 use std::hash::Hash;
 
 use regex::Regex;
@@ -33,29 +33,54 @@ pub struct Graph<T> {
     children: Vec<Rc<RefCell<Node<T>>>>,
 }
 
+/// Wraps a node so `sort_by`'s user-supplied comparator can drive a
+/// `BinaryHeap`: the heap's max (next popped) is the comparator's minimum,
+/// so `cmp` delegates to the handler in reverse.
+struct HeapNode<'a, T> {
+    node: Rc<RefCell<Node<T>>>,
+    handler: &'a dyn Fn(&ReadOnly<Node<T>>, &ReadOnly<Node<T>>) -> Ordering,
+}
+
+impl<'a, T> PartialEq for HeapNode<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T> Eq for HeapNode<'a, T> {}
+
+impl<'a, T> PartialOrd for HeapNode<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T> Ord for HeapNode<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.handler)(&self.node.clone().into(), &other.node.clone().into()).reverse()
+    }
+}
+
 impl <T: Eq + Hash> Graph<T> {
     pub fn sort_by(&mut self, handler: impl Fn(&ReadOnly<Node<T>>, &ReadOnly<Node<T>>) -> Ordering) {
-        let mut order: Vec<Rc<RefCell<Node<T>>>> = Default::default();
-        let mut learnable_nodes = self.children.clone();
+        let mut order: Vec<Rc<RefCell<Node<T>>>> = Vec::with_capacity(self.children.len());
         let mut parents_learned: HashMap<NodeId, usize> = Default::default();
-        while !learnable_nodes.is_empty() {
-            learnable_nodes.sort_by(|a, b| {
-                handler(&a.clone().into(), &b.clone().into())
-            });
-            learnable_nodes.reverse();
-            let next = learnable_nodes.pop().unwrap();
-            {
-                let next = next.deref().borrow();
-                for child in &next.children {
-                    let learnable = {
-                        let child = child.deref().borrow();
-                        let count = parents_learned.entry(child.id).or_insert(0);
-                        *count += 1;
-                        *count == child.parents.len()
-                    };
-                    if learnable {
-                        learnable_nodes.push(child.clone());
-                    }
+
+        let mut learnable_nodes: BinaryHeap<HeapNode<T>> = self.children.iter()
+            .map(|node| HeapNode { node: node.clone(), handler: &handler })
+            .collect();
+
+        while let Some(HeapNode { node: next, .. }) = learnable_nodes.pop() {
+            let children = next.deref().borrow().children.clone();
+            for child in children {
+                let learnable = {
+                    let child = child.deref().borrow();
+                    let count = parents_learned.entry(child.id).or_insert(0);
+                    *count += 1;
+                    *count == child.parents.len()
+                };
+                if learnable {
+                    learnable_nodes.push(HeapNode { node: child, handler: &handler });
                 }
             }
             order.push(next);
@@ -74,6 +99,8 @@ pub struct Node<T> {
     val: T,
     parents: Vec<Rc<RefCell<Node<T>>>>,
     children: Vec<Rc<RefCell<Node<T>>>>,
+    descendent_count_cache: RefCell<Option<usize>>,
+    ancestor_count_cache: RefCell<Option<usize>>,
 }
 
 impl <T> Node<T> {
@@ -89,22 +116,73 @@ impl <T> Node<T> {
         self.parents.iter().map(|c| ReadOnly::from(c.clone())).collect::<Vec<_>>()
     }
 
+    /// Iterates every unique descendant reachable from this node, closest
+    /// first (BFS), deduplicating nodes shared by more than one parent.
+    pub fn descendants(&self) -> Traversal<T, impl Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>>> {
+        Traversal::new(&self.children, |n: &Node<T>| n.children.clone())
+    }
+
+    /// Iterates every unique ancestor reachable from this node, closest
+    /// first (BFS), deduplicating components shared by more than one child.
+    pub fn ancestors(&self) -> Traversal<T, impl Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>>> {
+        Traversal::new(&self.parents, |n: &Node<T>| n.parents.clone())
+    }
+
     pub fn descendent_len(&self) -> usize {
-        let children = &self.children;
-        let mut len = children.len();
-        for child in children {
-            len += child.deref().borrow().descendent_len()
+        if let Some(count) = *self.descendent_count_cache.borrow() {
+            return count;
         }
-        len
+        let count = self.descendants().count();
+        *self.descendent_count_cache.borrow_mut() = Some(count);
+        count
     }
 
     pub fn ancestor_len(&self) -> usize {
-        let parents = &self.parents;
-        let mut len = parents.len();
-        for parent in parents {
-            len += parent.deref().borrow().ancestor_len()
+        if let Some(count) = *self.ancestor_count_cache.borrow() {
+            return count;
+        }
+        let count = self.ancestors().count();
+        *self.ancestor_count_cache.borrow_mut() = Some(count);
+        count
+    }
+}
+
+/// A reusable, stack-safe BFS over a node's `children` or `parents` edges
+/// that deduplicates shared nodes via an explicit visited set, instead of
+/// recursing (and double-counting) straight down a diamond-shaped DAG.
+pub struct Traversal<T, F> {
+    queue: VecDeque<Rc<RefCell<Node<T>>>>,
+    visited: HashSet<NodeId>,
+    neighbors: F,
+}
+
+impl<T, F> Traversal<T, F>
+    where F: Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>> {
+    fn new(start: &[Rc<RefCell<Node<T>>>], neighbors: F) -> Self {
+        Traversal {
+            queue: start.iter().cloned().collect(),
+            visited: Default::default(),
+            neighbors,
+        }
+    }
+}
+
+impl<T, F> Iterator for Traversal<T, F>
+    where F: Fn(&Node<T>) -> Vec<Rc<RefCell<Node<T>>>> {
+    type Item = ReadOnly<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.queue.pop_front() {
+            let (id, next) = {
+                let n = node.deref().borrow();
+                (n.id, (self.neighbors)(&n))
+            };
+            if self.visited.insert(id) {
+                self.queue.extend(next);
+                return Some(ReadOnly::from(node));
+            }
         }
-        len
+        None
     }
 }
 
@@ -233,6 +311,8 @@ fn parse_characters() -> Result<Graph<char>> {
                 val: character,
                 parents: Default::default(),
                 children: Default::default(),
+                descendent_count_cache: Default::default(),
+                ancestor_count_cache: Default::default(),
             }));
             nodes.insert(character, node);
 
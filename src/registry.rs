@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::Term;
+
+/// Inverted indexes over a parsed term corpus, so a study UI can look terms
+/// up by exact writing/reading or search their glosses by keyword instead of
+/// only being able to bucket them by character.
+///
+/// `Registry` holds only the index (term positions), not the terms
+/// themselves, so it carries no lifetime tied to the corpus and can be
+/// built once and stored alongside it — every lookup takes the same
+/// `terms` slice it was built from to resolve positions back to `&Term`.
+pub struct Registry {
+    by_writing: HashMap<String, Vec<usize>>,
+    by_reading: HashMap<String, Vec<usize>>,
+    by_meaning_keyword: HashMap<String, Vec<usize>>,
+    popular_only: bool,
+}
+
+impl Registry {
+    pub fn build(terms: &[Term]) -> Self {
+        let mut by_writing: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_reading: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_meaning_keyword: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (i, term) in terms.iter().enumerate() {
+            for writing in &term.writings {
+                by_writing.entry(writing.clone()).or_insert_with(Vec::new).push(i);
+            }
+
+            for reading in term.readings.iter().flatten() {
+                by_reading.entry(reading.clone()).or_insert_with(Vec::new).push(i);
+            }
+
+            for meaning in &term.meanings {
+                for keyword in tokenize(meaning) {
+                    by_meaning_keyword.entry(keyword).or_insert_with(Vec::new).push(i);
+                }
+            }
+        }
+
+        Registry {
+            by_writing,
+            by_reading,
+            by_meaning_keyword,
+            popular_only: false,
+        }
+    }
+
+    /// When set, every lookup below is restricted to entries marked `(P)`.
+    pub fn set_popular_only(&mut self, popular_only: bool) {
+        self.popular_only = popular_only;
+    }
+
+    pub fn by_writing<'a>(&self, writing: &str, terms: &'a [Term]) -> Vec<&'a Term> {
+        self.resolve(self.by_writing.get(writing), terms)
+    }
+
+    pub fn by_reading<'a>(&self, reading: &str, terms: &'a [Term]) -> Vec<&'a Term> {
+        self.resolve(self.by_reading.get(reading), terms)
+    }
+
+    pub fn search_meaning<'a>(&self, query: &str, terms: &'a [Term]) -> Vec<&'a Term> {
+        let keyword = normalize(query);
+        self.resolve(self.by_meaning_keyword.get(&keyword), terms)
+    }
+
+    fn resolve<'a>(&self, indices: Option<&Vec<usize>>, terms: &'a [Term]) -> Vec<&'a Term> {
+        indices.map(|indices| {
+            indices.iter()
+                .map(|&i| &terms[i])
+                .filter(|term| !self.popular_only || term.popular)
+                .collect()
+        }).unwrap_or_default()
+    }
+}
+
+fn normalize(token: &str) -> String {
+    token.trim().to_lowercase()
+}
+
+fn tokenize(meaning: &str) -> Vec<String> {
+    meaning.split(|c: char| !c.is_alphanumeric())
+        .map(normalize)
+        .filter(|token| !token.is_empty())
+        .collect()
+}
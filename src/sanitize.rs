@@ -0,0 +1,96 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, HtmlElement};
+
+use crate::Result;
+
+// Mnemonic notes may carry furigana and light emphasis/links, but nothing
+// that can execute script or load external content.
+const ALLOWED_TAGS: &[&str] = &["ruby", "rt", "rp", "b", "em", "a"];
+const REMOVE_ENTIRELY: &[&str] = &["script", "style", "img"];
+
+/// Parses `raw_html` and returns it re-serialized with every tag and attribute
+/// stripped down to the mnemonic-note allow-list, so it is safe to pass to
+/// `set_inner_html`.
+pub fn sanitize_fragment(document: &Document, raw_html: &str) -> Result<String> {
+    let container: HtmlElement = document.create_element("div")?.unchecked_into();
+    container.set_inner_html(raw_html);
+    sanitize_children(&container)?;
+    Ok(container.inner_html())
+}
+
+fn sanitize_children(parent: &Element) -> Result<()> {
+    let children = parent.children();
+    let mut i = children.length();
+    while i > 0 {
+        i -= 1;
+        if let Some(el) = children.item(i) {
+            sanitize_element(&el)?;
+        }
+    }
+    Ok(())
+}
+
+fn sanitize_element(el: &Element) -> Result<()> {
+    let tag = el.tag_name().to_lowercase();
+
+    if REMOVE_ENTIRELY.contains(&tag.as_str()) {
+        if let Some(parent) = el.parent_node() {
+            parent.remove_child(el)?;
+        }
+        return Ok(());
+    }
+
+    // Sanitize descendants before deciding whether to unwrap `el` itself, so
+    // any disallowed markup nested inside is already cleaned up by the time
+    // it gets hoisted up into the parent.
+    sanitize_children(el)?;
+
+    if ALLOWED_TAGS.contains(&tag.as_str()) {
+        strip_disallowed_attributes(el)?;
+    } else {
+        unwrap_element(el)?;
+    }
+
+    Ok(())
+}
+
+fn unwrap_element(el: &Element) -> Result<()> {
+    if let Some(parent) = el.parent_node() {
+        while let Some(child) = el.first_child() {
+            parent.insert_before(&child, Some(el))?;
+        }
+        parent.remove_child(el)?;
+    }
+    Ok(())
+}
+
+fn strip_disallowed_attributes(el: &Element) -> Result<()> {
+    let is_anchor = el.tag_name().eq_ignore_ascii_case("a");
+    let attrs = el.attributes();
+    let mut i = attrs.length();
+    while i > 0 {
+        i -= 1;
+        if let Some(attr) = attrs.item(i) {
+            let name = attr.name().to_lowercase();
+            let keep = is_anchor && name == "href" && is_safe_href(&attr.value());
+            if !keep {
+                el.remove_attribute(&name)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_safe_href(value: &str) -> bool {
+    // Browsers strip ASCII tabs and newlines from a URL before parsing its
+    // scheme (see the WHATWG URL spec's "remove all ASCII tab or newline"
+    // step), so a sanitizer that only trims the ends can be bypassed with
+    // e.g. `java&#9;script:`. Strip them the same way before comparing.
+    let scheme: String = value
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .to_lowercase();
+    !scheme.starts_with("javascript:") && !scheme.starts_with("data:") && !scheme.starts_with("vbscript:")
+}
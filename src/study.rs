@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use web_sys::Storage;
+
+use crate::error::Error;
+use crate::Result;
+
+const STORAGE_KEY: &str = "kanji_companion.review_state";
+const MS_PER_DAY: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// Per-character spaced-repetition state, scheduled with the SM-2 algorithm.
+#[derive(Debug, Clone)]
+pub struct ReviewState {
+    pub ease_factor: f64,
+    pub repetitions: u32,
+    pub interval_days: u32,
+    pub due_at: f64,
+}
+
+impl ReviewState {
+    pub fn new() -> Self {
+        ReviewState {
+            ease_factor: 2.5,
+            repetitions: 0,
+            interval_days: 0,
+            due_at: 0.0,
+        }
+    }
+
+    pub fn is_due(&self, now: f64) -> bool {
+        self.due_at <= now
+    }
+
+    pub fn is_learned(&self) -> bool {
+        self.repetitions > 0
+    }
+
+    /// Applies an SM-2 review graded `quality` (0-5) at time `now` (millis since epoch).
+    pub fn review(&mut self, quality: u8, now: f64) {
+        let q = quality.min(5) as f64;
+
+        if q < 3.0 {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval_days = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval_days as f64 * self.ease_factor).round() as u32,
+            };
+        }
+
+        self.ease_factor = (self.ease_factor + (0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02))).max(1.3);
+        self.due_at = now + self.interval_days as f64 * MS_PER_DAY;
+    }
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        ReviewState::new()
+    }
+}
+
+/// Persists `ReviewState` per character `writing` to `localStorage`.
+pub struct ReviewStore {
+    states: HashMap<char, ReviewState>,
+}
+
+impl ReviewStore {
+    /// Loads the store from `localStorage`, starting empty if nothing was saved yet.
+    pub fn load() -> Result<Self> {
+        let storage = local_storage()?;
+        let states = match storage.get_item(STORAGE_KEY)? {
+            Some(json) => parse_states(&json),
+            None => HashMap::new(),
+        };
+        Ok(ReviewStore { states })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let storage = local_storage()?;
+        storage.set_item(STORAGE_KEY, &serialize_states(&self.states))?;
+        Ok(())
+    }
+
+    pub fn get(&self, writing: char) -> ReviewState {
+        self.states.get(&writing).cloned().unwrap_or_default()
+    }
+
+    pub fn is_due(&self, writing: char, now: f64) -> bool {
+        self.states.get(&writing).map(|s| s.is_due(now)).unwrap_or(true)
+    }
+
+    pub fn is_learned(&self, writing: char) -> bool {
+        self.states.get(&writing).map(|s| s.is_learned()).unwrap_or(false)
+    }
+
+    pub fn record_review(&mut self, writing: char, quality: u8, now: f64) {
+        self.states.entry(writing).or_insert_with(ReviewState::new).review(quality, now);
+    }
+}
+
+fn local_storage() -> Result<Storage> {
+    web_sys::window()
+        .ok_or(Error::WindowNotFound)?
+        .local_storage()?
+        .ok_or(Error::StorageNotFound)
+}
+
+fn serialize_states(states: &HashMap<char, ReviewState>) -> String {
+    let entries = states.iter().map(|(writing, state)| {
+        format!(
+            "{{\"writing\":\"{}\",\"ef\":{},\"n\":{},\"interval\":{},\"due\":{}}}",
+            writing, state.ease_factor, state.repetitions, state.interval_days, state.due_at
+        )
+    }).collect::<Vec<_>>().join(",");
+    format!("[{}]", entries)
+}
+
+fn parse_states(json: &str) -> HashMap<char, ReviewState> {
+    let entry_re = Regex::new(
+        r#""writing":"(.)","ef":([0-9.]+),"n":(\d+),"interval":(\d+),"due":([0-9.]+)"#
+    ).unwrap();
+
+    entry_re.captures_iter(json).map(|cap| {
+        let writing = cap[1].chars().next().unwrap();
+        let state = ReviewState {
+            ease_factor: cap[2].parse().unwrap_or(2.5),
+            repetitions: cap[3].parse().unwrap_or(0),
+            interval_days: cap[4].parse().unwrap_or(0),
+            due_at: cap[5].parse().unwrap_or(0.0),
+        };
+        (writing, state)
+    }).collect()
+}